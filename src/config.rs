@@ -0,0 +1,63 @@
+//! User config file, loaded from `$XDG_CONFIG_HOME/yabai-cycle-spaces/config.toml`
+//! (falling back to `~/.config` when `XDG_CONFIG_HOME` is unset), mirroring how
+//! other terminal tools keep their persistent settings outside the binary.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::ProgramError;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Scope {
+    #[default]
+    Display,
+    Global,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case", default)]
+pub(crate) struct Config {
+    pub(crate) wrap: bool,
+    pub(crate) scope: Scope,
+    pub(crate) bindings: HashMap<String, u32>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            wrap: true,
+            scope: Scope::Display,
+            bindings: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file if present, falling back to defaults (wrap
+    /// enabled, display-scoped cycling, no bindings) if it doesn't exist.
+    pub(crate) fn load() -> Result<Config, ProgramError> {
+        let path = config_path();
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Ok(toml::from_str(&contents)?),
+            Err(_) => Ok(Config::default()),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/yabai-cycle-spaces`, falling back to `~/.config` when
+/// `XDG_CONFIG_HOME` is unset. Shared with the MRU history file, which
+/// lives alongside the config file rather than under its own directory.
+pub(crate) fn config_dir() -> PathBuf {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default();
+
+    config_home.join("yabai-cycle-spaces")
+}
+
+fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
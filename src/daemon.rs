@@ -0,0 +1,196 @@
+//! Long-running daemon that keeps a single `YabaiSpaceConfig` warm instead
+//! of re-querying `yabai -m query --spaces` on every cycle keypress.
+//!
+//! Signals and client requests both arrive over the same control socket
+//! and are merged onto one channel in the daemon's main loop - signals,
+//! stdin and timers being separate input streams fed into a single event
+//! loop is a common shape for this kind of long-running tool, just with
+//! yabai's signal mechanism standing in for stdin/timers here.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc;
+
+use crate::{
+    config::Config, mru_last, mru_walk, yabai_cycle_to, yabai_move_space, yabai_query_spaces,
+    ProgramError, YabaiSpace,
+};
+
+/// A request sent by a thin client over the control socket.
+#[derive(Debug, Clone)]
+pub(crate) enum ClientCommand {
+    Next,
+    Previous,
+    CycleTo(String),
+    Last,
+    MruNext,
+    MruPrev,
+}
+
+impl ClientCommand {
+    fn encode(&self) -> String {
+        match self {
+            ClientCommand::Next => "next".to_string(),
+            ClientCommand::Previous => "prev".to_string(),
+            ClientCommand::CycleTo(query) => format!("cycle_to:{query}"),
+            ClientCommand::Last => "last".to_string(),
+            ClientCommand::MruNext => "mru_next".to_string(),
+            ClientCommand::MruPrev => "mru_prev".to_string(),
+        }
+    }
+
+    fn decode(line: &str) -> Option<ClientCommand> {
+        match line {
+            "next" => Some(ClientCommand::Next),
+            "prev" => Some(ClientCommand::Previous),
+            "last" => Some(ClientCommand::Last),
+            "mru_next" => Some(ClientCommand::MruNext),
+            "mru_prev" => Some(ClientCommand::MruPrev),
+            _ => line
+                .strip_prefix("cycle_to:")
+                .map(|query| ClientCommand::CycleTo(query.to_string())),
+        }
+    }
+}
+
+/// One item off the merged event channel: either a client asking for a
+/// focus change, or a signal telling us yabai's own state moved and our
+/// cache needs a refresh.
+enum Event {
+    Client(ClientCommand, UnixStream),
+    Signal,
+}
+
+fn socket_path() -> PathBuf {
+    std::env::temp_dir().join("yabai-cycle-spaces.sock")
+}
+
+/// Sends a request to a running daemon and waits for its "ok"/"error" reply.
+/// Fails (so the caller can fall back to the one-shot path) if no daemon is
+/// listening, or if the daemon reports that it couldn't carry out the
+/// request.
+pub(crate) fn send_command(command: ClientCommand) -> std::io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    writeln!(stream, "{}", command.encode())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+
+    match reply.trim() {
+        "ok" => Ok(()),
+        _ => Err(std::io::Error::other(
+            "daemon reported an error handling the request",
+        )),
+    }
+}
+
+/// Pokes a running daemon to tell it yabai's state changed. Called by our
+/// own `--notify-signal` invocation, which yabai runs as the action for
+/// the signals we register in `register_signals`.
+pub(crate) fn notify_signal() -> std::io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    writeln!(stream, "signal")
+}
+
+/// Stable label for our yabai signal handlers, so a restarting daemon can
+/// find and remove its own previously-registered handlers instead of
+/// piling up duplicates alongside them.
+const SIGNAL_LABEL: &str = "yabai-cycle-spaces";
+
+/// Removes this binary's previously-registered signal handlers (if any)
+/// before re-adding them, so daemon restarts don't leave yabai re-invoking
+/// us once per stale registration on every space/display change.
+fn register_signals() -> Result<(), ProgramError> {
+    let exe = std::env::current_exe()?;
+    let exe = exe.to_string_lossy();
+
+    for event in ["space_changed", "display_changed"] {
+        let label = format!("{SIGNAL_LABEL}-{event}");
+
+        let _ = Command::new("yabai")
+            .arg("-m")
+            .arg("signal")
+            .arg("--remove")
+            .arg(&label)
+            .output();
+
+        Command::new("yabai")
+            .arg("-m")
+            .arg("signal")
+            .arg("--add")
+            .arg(format!("label={label}"))
+            .arg(format!("event={event}"))
+            .arg(format!("action=\"{exe}\" --notify-signal"))
+            .output()?;
+    }
+
+    Ok(())
+}
+
+fn handle_connection(stream: UnixStream, tx: &mpsc::Sender<Event>) -> std::io::Result<()> {
+    let mut line = String::new();
+    BufReader::new(stream.try_clone()?).read_line(&mut line)?;
+
+    let event = match line.trim() {
+        "signal" => Event::Signal,
+        line => match ClientCommand::decode(line) {
+            Some(command) => Event::Client(command, stream),
+            None => return Ok(()),
+        },
+    };
+
+    let _ = tx.send(event);
+    Ok(())
+}
+
+/// Runs the daemon: queries yabai once, then serves client requests against
+/// the cached config, refreshing it whenever a registered yabai signal
+/// fires to tell us the space layout moved underneath us.
+pub(crate) fn run(settings: Config) -> Result<(), ProgramError> {
+    let path = socket_path();
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+
+    register_signals()?;
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                let _ = handle_connection(stream, &tx);
+            });
+        }
+    });
+
+    let mut cached = yabai_query_spaces()?;
+
+    for event in rx {
+        match event {
+            Event::Signal => match yabai_query_spaces() {
+                Ok(spaces) => cached = spaces,
+                Err(err) => eprintln!("yabai-cycle-spaces: failed to refresh spaces, keeping last-known layout: {err}"),
+            },
+            Event::Client(command, mut reply) => {
+                let result = match command {
+                    ClientCommand::Next => yabai_move_space(&cached, YabaiSpace::Next, &settings),
+                    ClientCommand::Previous => {
+                        yabai_move_space(&cached, YabaiSpace::Previous, &settings)
+                    }
+                    ClientCommand::CycleTo(query) => {
+                        yabai_cycle_to(&mut cached, &settings, &query)
+                    }
+                    ClientCommand::Last => mru_last(&cached),
+                    ClientCommand::MruNext => mru_walk(&cached, -1),
+                    ClientCommand::MruPrev => mru_walk(&cached, 1),
+                };
+                let _ = writeln!(reply, "{}", if result.is_ok() { "ok" } else { "error" });
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Per-display most-recently-used space history, persisted to disk so it
+//! survives between invocations (each cycle keypress is its own process).
+//! Backs `--last` (toggle to the previously focused space) and
+//! `--mru-prev`/`--mru-next` (walk further back/forward through it).
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::config;
+
+const RING_CAPACITY: usize = 32;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub(crate) struct History {
+    per_display: HashMap<u32, VecDeque<u32>>,
+    cursor: HashMap<u32, usize>,
+}
+
+impl History {
+    /// Loads the history file, or an empty history if it doesn't exist yet
+    /// or fails to parse - losing MRU history isn't worth failing a cycle
+    /// command over.
+    pub(crate) fn load() -> History {
+        std::fs::read_to_string(history_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self) -> std::io::Result<()> {
+        let path = history_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let contents = serde_json::to_string(self).unwrap_or_default();
+        std::fs::write(path, contents)
+    }
+
+    /// Records that `space_id` was just left on `display`: pushes it to the
+    /// front of that display's ring (deduping it if already present) and
+    /// resets the walk cursor back to the most recent entry.
+    pub(crate) fn record_focus_change(&mut self, display: u32, space_id: u32) {
+        let ring = self.per_display.entry(display).or_default();
+        ring.retain(|id| *id != space_id);
+        ring.push_front(space_id);
+        ring.truncate(RING_CAPACITY);
+        self.cursor.insert(display, 0);
+    }
+
+    /// The space id `steps_back` entries deep in `display`'s history
+    /// (0 = the space most recently left on that display).
+    pub(crate) fn at(&self, display: u32, steps_back: usize) -> Option<u32> {
+        self.per_display.get(&display)?.get(steps_back).copied()
+    }
+
+    pub(crate) fn ring_len(&self, display: u32) -> usize {
+        self.per_display.get(&display).map_or(0, VecDeque::len)
+    }
+
+    pub(crate) fn cursor(&self, display: u32) -> usize {
+        self.cursor.get(&display).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn set_cursor(&mut self, display: u32, steps_back: usize) {
+        self.cursor.insert(display, steps_back);
+    }
+}
+
+fn history_path() -> std::path::PathBuf {
+    config::config_dir().join("history.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn records_most_recent_first() {
+        let mut history = History::default();
+        history.record_focus_change(1, 10);
+        history.record_focus_change(1, 20);
+
+        assert_eq!(history.at(1, 0), Some(20));
+        assert_eq!(history.at(1, 1), Some(10));
+        assert_eq!(history.at(1, 2), None);
+    }
+
+    #[test]
+    fn recording_dedupes_existing_entries() {
+        let mut history = History::default();
+        history.record_focus_change(1, 10);
+        history.record_focus_change(1, 20);
+        history.record_focus_change(1, 10);
+
+        assert_eq!(history.ring_len(1), 2);
+        assert_eq!(history.at(1, 0), Some(10));
+        assert_eq!(history.at(1, 1), Some(20));
+    }
+
+    #[test]
+    fn displays_have_independent_rings() {
+        let mut history = History::default();
+        history.record_focus_change(1, 10);
+        history.record_focus_change(2, 20);
+
+        assert_eq!(history.at(1, 0), Some(10));
+        assert_eq!(history.at(2, 0), Some(20));
+        assert_eq!(history.ring_len(1), 1);
+    }
+
+    #[test]
+    fn recording_resets_the_cursor() {
+        let mut history = History::default();
+        history.record_focus_change(1, 10);
+        history.set_cursor(1, 5);
+        assert_eq!(history.cursor(1), 5);
+
+        history.record_focus_change(1, 20);
+        assert_eq!(history.cursor(1), 0);
+    }
+
+    #[test]
+    fn ring_is_bounded_by_capacity() {
+        let mut history = History::default();
+        for id in 0..super::RING_CAPACITY as u32 + 5 {
+            history.record_focus_change(1, id);
+        }
+
+        assert_eq!(history.ring_len(1), super::RING_CAPACITY);
+        // Most recently pushed id is still at the front.
+        assert_eq!(history.at(1, 0), Some(super::RING_CAPACITY as u32 + 4));
+    }
+}
@@ -1,14 +1,21 @@
 #[macro_use]
 extern crate serde_derive;
 
+mod config;
+mod daemon;
+mod history;
+mod matching;
+mod tui;
+
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::process::Command;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 enum ProgramError {
     #[error("yabai executable failed")]
     YabayExecutionError(#[from] std::io::Error),
@@ -17,11 +24,14 @@ enum ProgramError {
     #[error("illegal yabai state")]
     //YabaiConfigError(#[from] itertools::ExactlyOneError<_>),
     YabaiConfigError,
+    #[error("parsing config file failed")]
+    ConfigParseError(#[from] toml::de::Error),
 }
 
 enum YabaiSpace {
     Next,
     Previous,
+    #[allow(dead_code)]
     Space(u32),
 }
 
@@ -34,21 +44,56 @@ enum YabaiSpace {
 )]
 #[clap(group = clap::ArgGroup::new("cycle-group").multiple(false))]
 struct Arguments {
+    #[clap(subcommand)]
+    action: Option<Action>,
     #[clap(short, long, group = "cycle-group")]
     next: bool,
     #[clap(short, long, group = "cycle-group")]
     prev: bool,
     #[clap(long, group = "cycle-group")]
-    cycle_to: Option<u32>,
+    cycle_to: Option<String>,
+    #[clap(long, group = "cycle-group")]
+    filter: bool,
+    #[clap(short, long, group = "cycle-group")]
+    interactive: bool,
+    #[clap(long, group = "cycle-group")]
+    last: bool,
+    #[clap(long, group = "cycle-group")]
+    mru_next: bool,
+    #[clap(long, group = "cycle-group")]
+    mru_prev: bool,
+    /// Internal: invoked by the yabai signal we register for ourselves, to
+    /// tell a running daemon its cached state needs a refresh.
+    #[clap(long, hide = true)]
+    notify_signal: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Action {
+    /// Run as a long-lived daemon that keeps yabai state warm and serves
+    /// --next/--prev/--cycle-to requests from a cache kept fresh by yabai
+    /// signals, instead of re-querying yabai on every invocation.
+    Daemon,
 }
 
 #[derive(Debug)]
 struct YabaiSpaceConfig {
     display_space_map: HashMap<u32, Vec<u32>>,
     display_visible_map: HashMap<u32, u32>,
+    display_space_info: HashMap<u32, Vec<YabaiSpaceInfo>>,
     focused_display: u32,
 }
 
+#[derive(Debug, Clone)]
+struct YabaiSpaceInfo {
+    id: u32,
+    index: u32,
+    label: String,
+    window_count: usize,
+    windows: Vec<u32>,
+    app_names: Vec<String>,
+}
+
 // [{
 //    "id":1,
 //    "uuid":"",
@@ -69,12 +114,12 @@ struct YabaiSpaceConfig {
 struct YabaiSpaceConfigJson {
     id: u32,
     //uuid: String,
-    //index: i32,
-    //label: String,
+    index: u32,
+    label: String,
     //#[serde(rename(serialize = "type", deserialize = "type"))]
     //space_type: String,
     display: u32,
-    //windows: Vec<u32>,
+    windows: Vec<u32>,
     //first_window: u32,
     //last_window: u32,
     has_focus: bool,
@@ -82,6 +127,28 @@ struct YabaiSpaceConfigJson {
     //is_native_fullscreen: bool,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct YabaiWindowConfigJson {
+    id: u32,
+    app: String,
+}
+
+/// Maps window id -> owning app name, used to let `--cycle-to`/`--filter`
+/// match a space by the apps running in it, not just its label.
+fn yabai_query_window_apps() -> Result<HashMap<u32, String>, ProgramError> {
+    let output = Command::new("yabai")
+        .arg("-m")
+        .arg("query")
+        .arg("--windows")
+        .output()?;
+
+    let output_str = String::from_utf8_lossy(&output.stdout).to_string();
+    let windows: Vec<YabaiWindowConfigJson> = serde_json::from_str(output_str.as_str())?;
+
+    Ok(windows.into_iter().map(|w| (w.id, w.app)).collect())
+}
+
 fn yabai_query_spaces() -> Result<YabaiSpaceConfig, ProgramError> {
     let output = Command::new("yabai")
         .arg("-m")
@@ -97,6 +164,7 @@ fn yabai_query_spaces() -> Result<YabaiSpaceConfig, ProgramError> {
     let mut config = YabaiSpaceConfig {
         display_space_map: HashMap::new(),
         display_visible_map: HashMap::new(),
+        display_space_info: HashMap::new(),
         focused_display: all_spaces
             .iter()
             .filter(|s| s.has_focus)
@@ -108,6 +176,17 @@ fn yabai_query_spaces() -> Result<YabaiSpaceConfig, ProgramError> {
     for (display, spaces) in &all_spaces.into_iter().group_by(|s| s.display) {
         let spaces: Vec<YabaiSpaceConfigJson> = spaces.collect();
         let display_space_map = spaces.iter().map(|s| s.id).collect_vec();
+        let display_space_info = spaces
+            .iter()
+            .map(|s| YabaiSpaceInfo {
+                id: s.id,
+                index: s.index,
+                label: s.label.clone(),
+                window_count: s.windows.len(),
+                windows: s.windows.clone(),
+                app_names: Vec::new(),
+            })
+            .collect_vec();
 
         let focused_space = spaces
             .iter()
@@ -117,13 +196,36 @@ fn yabai_query_spaces() -> Result<YabaiSpaceConfig, ProgramError> {
 
         config.display_visible_map.insert(display, focused_space.id);
         config.display_space_map.insert(display, display_space_map);
+        config
+            .display_space_info
+            .insert(display, display_space_info);
     }
 
     Ok(config)
 }
 
-fn yabai_focus_space(display: u32, space_idx: u32) -> Result<(), ProgramError> {
-    let output = Command::new("yabai")
+/// Fills in each space's `app_names`, which `yabai_query_spaces` leaves
+/// empty - only fuzzy matching (`--cycle-to`'s fallback, `--filter`) needs
+/// app names, so plain cycling doesn't pay for a second `yabai` shell-out
+/// on every keypress.
+fn populate_app_names(config: &mut YabaiSpaceConfig) -> Result<(), ProgramError> {
+    let window_apps = yabai_query_window_apps()?;
+
+    for spaces in config.display_space_info.values_mut() {
+        for space in spaces {
+            space.app_names = space
+                .windows
+                .iter()
+                .filter_map(|w| window_apps.get(w).cloned())
+                .collect();
+        }
+    }
+
+    Ok(())
+}
+
+fn yabai_focus_space(_display: u32, space_idx: u32) -> Result<(), ProgramError> {
+    let _output = Command::new("yabai")
         .arg("-m")
         .arg("space")
         .arg("--focus")
@@ -133,17 +235,68 @@ fn yabai_focus_space(display: u32, space_idx: u32) -> Result<(), ProgramError> {
     Ok(())
 }
 
-fn yabai_move_space(config: &YabaiSpaceConfig, cmd: YabaiSpace) -> Result<(), ProgramError> {
+/// Focuses a space, first recording the space it's replacing into that
+/// display's MRU history so `--last`/`--mru-next`/`--mru-prev` can get back
+/// to it later.
+fn focus_space_with_history(
+    config: &YabaiSpaceConfig,
+    display: u32,
+    space_idx: u32,
+) -> Result<(), ProgramError> {
+    if let Some(previous) = config.display_visible_map.get(&display).copied() {
+        let mut history = history::History::load();
+        history.record_focus_change(display, previous);
+        history.save()?;
+    }
+
+    yabai_focus_space(display, space_idx)
+}
+
+/// Steps `index` by `delta` within `[0, len)`, wrapping around the ends when
+/// `wrap` is set and clamping to the edges otherwise.
+fn step_space_index(index: usize, delta: isize, len: usize, wrap: bool) -> usize {
+    if len == 0 {
+        return 0;
+    }
+
+    let stepped = index as isize + delta;
+    if wrap {
+        stepped.rem_euclid(len as isize) as usize
+    } else {
+        stepped.clamp(0, len as isize - 1) as usize
+    }
+}
+
+fn yabai_move_space(
+    config: &YabaiSpaceConfig,
+    cmd: YabaiSpace,
+    settings: &config::Config,
+) -> Result<(), ProgramError> {
+    match settings.scope {
+        config::Scope::Display => move_within_focused_display(config, cmd, settings),
+        config::Scope::Global => move_across_all_displays(config, cmd, settings),
+    }
+}
+
+/// `scope = "display"`: cycles only through the spaces on the focused
+/// display, one focus call to wherever the step lands.
+fn move_within_focused_display(
+    config: &YabaiSpaceConfig,
+    cmd: YabaiSpace,
+    settings: &config::Config,
+) -> Result<(), ProgramError> {
+    let display = config.focused_display;
+
     let focused_space = config
         .display_visible_map
-        .get(&config.focused_display)
+        .get(&display)
         .ok_or(ProgramError::YabaiConfigError)?;
-    let selected_display_spaces = config
+    let spaces = config
         .display_space_map
-        .get(&config.focused_display)
+        .get(&display)
         .ok_or(ProgramError::YabaiConfigError)?;
 
-    let space_index = selected_display_spaces
+    let space_index = spaces
         .iter()
         .enumerate()
         .filter(|(_, s)| *s == focused_space)
@@ -151,39 +304,247 @@ fn yabai_move_space(config: &YabaiSpaceConfig, cmd: YabaiSpace) -> Result<(), Pr
         .exactly_one()
         .map_err(|_| ProgramError::YabaiConfigError)?;
 
-    let new_space = match cmd {
-        YabaiSpace::Next => space_index + 1,
-        YabaiSpace::Previous => space_index - 1,
+    let previous_spaces: usize = (0u32..display - 1)
+        .filter_map(|d| config.display_space_map.get(&(d + 1)))
+        .map(|sv| sv.len())
+        .sum();
+
+    let new_index = match cmd {
+        YabaiSpace::Next => step_space_index(space_index, 1, spaces.len(), settings.wrap),
+        YabaiSpace::Previous => step_space_index(space_index, -1, spaces.len(), settings.wrap),
         YabaiSpace::Space(s) => s as usize,
     };
 
-    for (display, spaces) in config.display_space_map.iter() {
-        let previous_spaces: usize = (0u32..*display - 1)
-            .into_iter()
-            .filter_map(|d| config.display_space_map.get(&(d + 1)))
-            .map(|sv| sv.len())
-            .sum();
-        let new_space = previous_spaces as u32 + (new_space % spaces.len()) as u32;
-        println!("selected space : {}", new_space);
-        yabai_focus_space(*display, new_space)?;
+    let new_space = previous_spaces as u32 + new_index as u32;
+    println!("selected space : {}", new_space);
+    focus_space_with_history(config, display, new_space)
+}
+
+/// `scope = "global"`: treats every display's spaces as one linear
+/// ordering (yabai's own global `index`) and makes a single focus call to
+/// wherever the step lands, rather than moving every display at once.
+fn move_across_all_displays(
+    config: &YabaiSpaceConfig,
+    cmd: YabaiSpace,
+    settings: &config::Config,
+) -> Result<(), ProgramError> {
+    let mut all_spaces: Vec<(u32, &YabaiSpaceInfo)> = config
+        .display_space_info
+        .iter()
+        .flat_map(|(display, spaces)| spaces.iter().map(move |info| (*display, info)))
+        .collect();
+    all_spaces.sort_by_key(|(_, info)| info.index);
+
+    let focused_id = config
+        .display_visible_map
+        .get(&config.focused_display)
+        .ok_or(ProgramError::YabaiConfigError)?;
+
+    let current = all_spaces
+        .iter()
+        .position(|(_, info)| info.id == *focused_id)
+        .ok_or(ProgramError::YabaiConfigError)?;
+
+    let new_position = match cmd {
+        YabaiSpace::Next => step_space_index(current, 1, all_spaces.len(), settings.wrap),
+        YabaiSpace::Previous => step_space_index(current, -1, all_spaces.len(), settings.wrap),
+        YabaiSpace::Space(s) => s as usize,
+    };
+
+    let (target_display, target) = all_spaces
+        .get(new_position)
+        .ok_or(ProgramError::YabaiConfigError)?;
+    let new_space = target.index - 1;
+
+    println!("selected space : {}", new_space);
+    focus_space_with_history(config, *target_display, new_space)
+}
+
+/// Finds the `(display, space_idx)` of a space by its raw yabai space id, as
+/// used by `[bindings]` entries in the config file and the MRU history.
+/// `space_idx` is the 0-based index `yabai_focus_space` expects - yabai's
+/// own global `index` field minus one - not a position within the
+/// display's own space list.
+fn locate_space_id(config: &YabaiSpaceConfig, space_id: u32) -> Option<(u32, u32)> {
+    config
+        .display_space_info
+        .iter()
+        .find_map(|(display, spaces)| {
+            spaces
+                .iter()
+                .find(|s| s.id == space_id)
+                .map(|s| (*display, s.index - 1))
+        })
+}
+
+fn yabai_cycle_to(
+    config: &mut YabaiSpaceConfig,
+    settings: &config::Config,
+    query: &str,
+) -> Result<(), ProgramError> {
+    if let Some(space_id) = settings.bindings.get(query).copied() {
+        return match locate_space_id(config, space_id) {
+            Some((display, space_idx)) => focus_space_with_history(config, display, space_idx),
+            None => {
+                println!(
+                    "'{}' is bound to space {} but it no longer exists",
+                    query, space_id
+                );
+                Ok(())
+            }
+        };
     }
 
-    Ok(())
+    populate_app_names(config)?;
+
+    match matching::ranked_matches(config, query).into_iter().next() {
+        Some(m) => {
+            println!("cycle to '{}' -> space {}", query, m.info.index);
+            focus_space_with_history(config, m.display, m.info.index - 1)
+        }
+        None => {
+            println!("no space matches '{}'", query);
+            Ok(())
+        }
+    }
+}
+
+/// `--last`: toggles focus back to the space most recently left on the
+/// focused display, swapping it with the current one in the history ring.
+fn mru_last(config: &YabaiSpaceConfig) -> Result<(), ProgramError> {
+    let display = config.focused_display;
+    let history = history::History::load();
+
+    match history.at(display, 0) {
+        Some(space_id) => match locate_space_id(config, space_id) {
+            Some((display, space_idx)) => focus_space_with_history(config, display, space_idx),
+            None => {
+                println!("space from history no longer exists");
+                Ok(())
+            }
+        },
+        None => {
+            println!("no MRU history yet for this display");
+            Ok(())
+        }
+    }
+}
+
+/// `--mru-next`/`--mru-prev`: walks `delta` steps through the focused
+/// display's history without disturbing it, the way repeatedly tapping
+/// alt-tab steps further back without reshuffling the list each time.
+fn mru_walk(config: &YabaiSpaceConfig, delta: isize) -> Result<(), ProgramError> {
+    let display = config.focused_display;
+    let mut history = history::History::load();
+
+    let depth = history.ring_len(display);
+    if depth == 0 {
+        println!("no MRU history yet for this display");
+        return Ok(());
+    }
+
+    let cursor = (history.cursor(display) as isize + delta).clamp(0, depth as isize - 1) as usize;
+    history.set_cursor(display, cursor);
+    history.save()?;
+
+    match history.at(display, cursor) {
+        Some(space_id) => match locate_space_id(config, space_id) {
+            Some((target_display, space_idx)) => yabai_focus_space(target_display, space_idx),
+            None => {
+                println!("space from history no longer exists");
+                Ok(())
+            }
+        },
+        None => {
+            println!("no MRU history yet for this display");
+            Ok(())
+        }
+    }
 }
 
 fn main() -> Result<(), ProgramError> {
     let args = Arguments::parse();
+    let settings = config::Config::load()?;
+
+    if args.notify_signal {
+        // Best-effort: if no daemon is listening there's nothing to notify.
+        let _ = daemon::notify_signal();
+        return Ok(());
+    }
 
-    let ys = yabai_query_spaces()?;
+    if let Some(Action::Daemon) = args.action {
+        return daemon::run(settings);
+    }
 
     if args.next {
-        yabai_move_space(&ys, YabaiSpace::Next)?;
+        if daemon::send_command(daemon::ClientCommand::Next).is_err() {
+            let ys = yabai_query_spaces()?;
+            yabai_move_space(&ys, YabaiSpace::Next, &settings)?;
+        }
     } else if args.prev {
-        yabai_move_space(&ys, YabaiSpace::Previous)?;
+        if daemon::send_command(daemon::ClientCommand::Previous).is_err() {
+            let ys = yabai_query_spaces()?;
+            yabai_move_space(&ys, YabaiSpace::Previous, &settings)?;
+        }
     } else if let Some(cycle_to) = args.cycle_to {
-        println!("cycle to {}", cycle_to);
-    } else {
+        if daemon::send_command(daemon::ClientCommand::CycleTo(cycle_to.clone())).is_err() {
+            let mut ys = yabai_query_spaces()?;
+            yabai_cycle_to(&mut ys, &settings, &cycle_to)?;
+        }
+    } else if args.last {
+        if daemon::send_command(daemon::ClientCommand::Last).is_err() {
+            let ys = yabai_query_spaces()?;
+            mru_last(&ys)?;
+        }
+    } else if args.mru_next {
+        if daemon::send_command(daemon::ClientCommand::MruNext).is_err() {
+            let ys = yabai_query_spaces()?;
+            mru_walk(&ys, -1)?;
+        }
+    } else if args.mru_prev {
+        if daemon::send_command(daemon::ClientCommand::MruPrev).is_err() {
+            let ys = yabai_query_spaces()?;
+            mru_walk(&ys, 1)?;
+        }
+    } else if args.filter {
+        let mut ys = yabai_query_spaces()?;
+        populate_app_names(&mut ys)?;
+        tui::run_filter(&ys)?;
+    } else if args.interactive {
+        let ys = yabai_query_spaces()?;
+        tui::run_picker(&ys)?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::step_space_index;
+
+    #[test]
+    fn wraps_past_the_end() {
+        assert_eq!(step_space_index(2, 1, 3, true), 0);
+    }
+
+    #[test]
+    fn wraps_past_the_start() {
+        assert_eq!(step_space_index(0, -1, 3, true), 2);
+    }
+
+    #[test]
+    fn clamps_at_the_end_when_not_wrapping() {
+        assert_eq!(step_space_index(2, 1, 3, false), 2);
+    }
+
+    #[test]
+    fn clamps_at_the_start_when_not_wrapping() {
+        assert_eq!(step_space_index(0, -1, 3, false), 0);
+    }
+
+    #[test]
+    fn empty_space_list_stays_at_zero() {
+        assert_eq!(step_space_index(0, 1, 0, true), 0);
+        assert_eq!(step_space_index(0, -1, 0, false), 0);
+    }
+}
@@ -0,0 +1,98 @@
+//! Fuzzy subsequence matching of a query against space labels and the app
+//! names of the windows they contain, shared by `--cycle-to` and `--filter`.
+
+use crate::{YabaiSpaceConfig, YabaiSpaceInfo};
+
+#[derive(Debug, Clone)]
+pub(crate) struct SpaceMatch {
+    pub(crate) display: u32,
+    pub(crate) info: YabaiSpaceInfo,
+    pub(crate) score: i64,
+}
+
+/// Scores `candidate` as a fuzzy subsequence match for `query`, case
+/// insensitively. `None` means `query` isn't a subsequence of `candidate`
+/// at all; an empty query matches everything. Lower scores are better
+/// matches, so typing "sl" scores "Slack" below a longer, looser match.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_lower = candidate.to_lowercase();
+    let mut chars = candidate_lower.chars().enumerate();
+    let mut total_gap: i64 = 0;
+    let mut last_pos: i64 = -1;
+
+    for q in query.to_lowercase().chars() {
+        let (pos, _) = chars.find(|&(_, c)| c == q)?;
+        let pos = pos as i64;
+        total_gap += if last_pos >= 0 {
+            pos - last_pos - 1
+        } else {
+            pos
+        };
+        last_pos = pos;
+    }
+
+    Some(total_gap)
+}
+
+/// All spaces that fuzzy-match `query` against their label or any of their
+/// windows' app names, sorted best match first.
+pub(crate) fn ranked_matches(config: &YabaiSpaceConfig, query: &str) -> Vec<SpaceMatch> {
+    let mut matches: Vec<SpaceMatch> = config
+        .display_space_info
+        .iter()
+        .flat_map(|(display, spaces)| spaces.iter().map(move |info| (*display, info)))
+        .filter_map(|(display, info)| {
+            let score = std::iter::once(info.label.as_str())
+                .chain(info.app_names.iter().map(String::as_str))
+                .filter_map(|candidate| fuzzy_score(query, candidate))
+                .min()?;
+
+            Some(SpaceMatch {
+                display,
+                info: info.clone(),
+                score,
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| m.score);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn empty_query_matches_everything_with_zero_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn non_subsequence_does_not_match() {
+        assert_eq!(fuzzy_score("xyz", "Slack"), None);
+    }
+
+    #[test]
+    fn tighter_match_scores_lower() {
+        let tight = fuzzy_score("sl", "Slack").unwrap();
+        let loose = fuzzy_score("sl", "Some Loose Match").unwrap();
+        assert!(tight < loose);
+    }
+
+    #[test]
+    fn is_case_insensitive() {
+        assert_eq!(fuzzy_score("SL", "slack"), fuzzy_score("sl", "slack"));
+    }
+
+    #[test]
+    fn counts_gaps_in_chars_not_bytes_for_multibyte_candidates() {
+        // "é" is two bytes in UTF-8 but one char; byte offsets would have
+        // inflated the gap before "a" here.
+        assert_eq!(fuzzy_score("ab", "éab"), Some(1));
+    }
+}
@@ -0,0 +1,376 @@
+//! Full-screen interactive space picker, entered via `--interactive`.
+//!
+//! One column per display, one row per space in that display. The
+//! currently visible space in each display is marked, the selected
+//! cell is highlighted, `Enter` focuses it and exits, `Esc`/`q` aborts.
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Terminal,
+};
+use std::io::stdout;
+
+use crate::matching::{self, SpaceMatch};
+use crate::{focus_space_with_history, ProgramError, YabaiSpaceConfig, YabaiSpaceInfo};
+
+/// Cursor position within the picker grid: which display column, and
+/// which row (index into that display's space list) is selected.
+struct Cursor {
+    displays: Vec<u32>,
+    display_idx: usize,
+    row: usize,
+}
+
+impl Cursor {
+    fn new(config: &YabaiSpaceConfig) -> Self {
+        let mut displays = config
+            .display_space_info
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        displays.sort_unstable();
+
+        let display_idx = displays
+            .iter()
+            .position(|d| *d == config.focused_display)
+            .unwrap_or(0);
+
+        let visible_id = displays
+            .get(display_idx)
+            .and_then(|d| config.display_visible_map.get(d));
+        let row = displays
+            .get(display_idx)
+            .and_then(|d| config.display_space_info.get(d))
+            .and_then(|spaces| spaces.iter().position(|s| Some(&s.id) == visible_id))
+            .unwrap_or(0);
+
+        Cursor {
+            displays,
+            display_idx,
+            row,
+        }
+    }
+
+    fn display(&self) -> u32 {
+        self.displays[self.display_idx]
+    }
+
+    fn rows(&self, config: &YabaiSpaceConfig) -> usize {
+        config
+            .display_space_info
+            .get(&self.display())
+            .map(Vec::len)
+            .unwrap_or(0)
+    }
+
+    fn move_display(&mut self, delta: isize, config: &YabaiSpaceConfig) {
+        let count = self.displays.len() as isize;
+        if count == 0 {
+            return;
+        }
+        self.display_idx = ((self.display_idx as isize + delta).rem_euclid(count)) as usize;
+        self.row = self.row.min(self.rows(config).saturating_sub(1));
+    }
+
+    fn move_row(&mut self, delta: isize, config: &YabaiSpaceConfig) {
+        let rows = self.rows(config) as isize;
+        if rows == 0 {
+            return;
+        }
+        self.row = ((self.row as isize + delta).rem_euclid(rows)) as usize;
+    }
+}
+
+/// The space currently under the cursor, if any.
+fn selected_space<'a>(config: &'a YabaiSpaceConfig, cursor: &Cursor) -> Option<&'a YabaiSpaceInfo> {
+    config
+        .display_space_info
+        .get(&cursor.display())
+        .and_then(|spaces| spaces.get(cursor.row))
+}
+
+/// Opens the full-screen picker, blocking until the user focuses a space
+/// (`Enter`) or aborts (`Esc`/`q`), mirroring the raw-mode/alternate-screen
+/// lifecycle used by other ratatui-based terminal apps.
+pub(crate) fn run_picker(config: &YabaiSpaceConfig) -> Result<(), ProgramError> {
+    enable_raw_mode()?;
+    let mut out = stdout();
+    execute!(out, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(out);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = picker_loop(&mut terminal, config);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn picker_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    config: &YabaiSpaceConfig,
+) -> Result<(), ProgramError> {
+    let mut cursor = Cursor::new(config);
+
+    loop {
+        terminal.draw(|frame| draw(frame, config, &cursor))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Left | KeyCode::Char('h') => cursor.move_display(-1, config),
+                KeyCode::Right | KeyCode::Char('l') => cursor.move_display(1, config),
+                KeyCode::Up | KeyCode::Char('k') => cursor.move_row(-1, config),
+                KeyCode::Down | KeyCode::Char('j') => cursor.move_row(1, config),
+                KeyCode::Enter => {
+                    if let Some(info) = selected_space(config, &cursor) {
+                        // `info.index` is yabai's global 1-based space index;
+                        // `yabai_focus_space` re-adds the 1 it wants back.
+                        focus_space_with_history(config, cursor.display(), info.index - 1)?;
+                    }
+                    return Ok(());
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut ratatui::Frame<'_>, config: &YabaiSpaceConfig, cursor: &Cursor) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            cursor
+                .displays
+                .iter()
+                .map(|_| Constraint::Ratio(1, cursor.displays.len().max(1) as u32))
+                .collect::<Vec<_>>(),
+        )
+        .split(frame.size());
+
+    for (col, display) in cursor.displays.iter().enumerate() {
+        let spaces = config
+            .display_space_info
+            .get(display)
+            .cloned()
+            .unwrap_or_default();
+        let visible_id = config.display_visible_map.get(display).copied();
+
+        let items: Vec<ListItem> = spaces
+            .iter()
+            .map(|space| {
+                let marker = if Some(space.id) == visible_id {
+                    "*"
+                } else {
+                    " "
+                };
+                let label = if space.label.is_empty() {
+                    format!("space {}", space.index)
+                } else {
+                    space.label.clone()
+                };
+                let text = format!(
+                    "{marker} {:>2}  {label}  ({} win)",
+                    space.index, space.window_count
+                );
+                ListItem::new(Line::raw(text))
+            })
+            .collect();
+
+        let mut state = ListState::default();
+        if col == cursor.display_idx {
+            state.select(Some(cursor.row));
+        }
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("display {display}")),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(list, columns[col], &mut state);
+    }
+}
+
+/// Opens a single-line fuzzy filter over space labels and window app names:
+/// each keystroke re-narrows the ranked match list, `Enter` focuses the
+/// current best match, `Esc` aborts without changing focus.
+pub(crate) fn run_filter(config: &YabaiSpaceConfig) -> Result<(), ProgramError> {
+    enable_raw_mode()?;
+    let mut out = stdout();
+    execute!(out, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(out);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = filter_loop(&mut terminal, config);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn filter_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    config: &YabaiSpaceConfig,
+) -> Result<(), ProgramError> {
+    let mut query = String::new();
+
+    loop {
+        let matches = matching::ranked_matches(config, &query);
+        terminal.draw(|frame| draw_filter(frame, &query, &matches))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc => return Ok(()),
+                KeyCode::Enter => {
+                    if let Some(best) = matches.first() {
+                        focus_space_with_history(config, best.display, best.info.index - 1)?;
+                    }
+                    return Ok(());
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Char(c) => query.push(c),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw_filter(frame: &mut ratatui::Frame<'_>, query: &str, matches: &[SpaceMatch]) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(frame.size());
+
+    let input = ratatui::widgets::Paragraph::new(Line::raw(query))
+        .block(Block::default().borders(Borders::ALL).title("filter"));
+    frame.render_widget(input, rows[0]);
+
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|m| {
+            let label = if m.info.label.is_empty() {
+                format!("space {}", m.info.index)
+            } else {
+                m.info.label.clone()
+            };
+            let text = format!(
+                "display {}  {:>2}  {label}  ({} win)",
+                m.display, m.info.index, m.info.window_count
+            );
+            ListItem::new(Line::raw(text))
+        })
+        .collect();
+
+    let mut state = ListState::default();
+    if !matches.is_empty() {
+        state.select(Some(0));
+    }
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("matches"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(list, rows[1], &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cursor;
+    use crate::{YabaiSpaceConfig, YabaiSpaceInfo};
+    use std::collections::HashMap;
+
+    fn space(id: u32, index: u32) -> YabaiSpaceInfo {
+        YabaiSpaceInfo {
+            id,
+            index,
+            label: String::new(),
+            window_count: 0,
+            windows: Vec::new(),
+            app_names: Vec::new(),
+        }
+    }
+
+    /// Two displays: display 1 has 3 spaces, display 2 has 2.
+    fn fixture() -> YabaiSpaceConfig {
+        let mut display_space_info = HashMap::new();
+        display_space_info.insert(1, vec![space(1, 1), space(2, 2), space(3, 3)]);
+        display_space_info.insert(2, vec![space(4, 4), space(5, 5)]);
+
+        YabaiSpaceConfig {
+            display_space_map: HashMap::new(),
+            display_visible_map: HashMap::new(),
+            display_space_info,
+            focused_display: 1,
+        }
+    }
+
+    #[test]
+    fn move_display_wraps_past_the_end() {
+        let config = fixture();
+        let mut cursor = Cursor::new(&config);
+        cursor.move_display(1, &config);
+        assert_eq!(cursor.display(), 2);
+        cursor.move_display(1, &config);
+        assert_eq!(cursor.display(), 1);
+    }
+
+    #[test]
+    fn move_display_wraps_past_the_start() {
+        let config = fixture();
+        let mut cursor = Cursor::new(&config);
+        cursor.move_display(-1, &config);
+        assert_eq!(cursor.display(), 2);
+    }
+
+    #[test]
+    fn move_display_clamps_row_to_the_new_displays_space_count() {
+        let config = fixture();
+        let mut cursor = Cursor::new(&config);
+        cursor.row = 2;
+        cursor.move_display(1, &config);
+        assert_eq!(cursor.display(), 2);
+        assert_eq!(cursor.row, 1);
+    }
+
+    #[test]
+    fn move_row_wraps_past_the_end() {
+        let config = fixture();
+        let mut cursor = Cursor::new(&config);
+        cursor.row = 2;
+        cursor.move_row(1, &config);
+        assert_eq!(cursor.row, 0);
+    }
+
+    #[test]
+    fn move_row_wraps_past_the_start() {
+        let config = fixture();
+        let mut cursor = Cursor::new(&config);
+        cursor.row = 0;
+        cursor.move_row(-1, &config);
+        assert_eq!(cursor.row, 2);
+    }
+}